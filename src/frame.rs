@@ -0,0 +1,155 @@
+//! Link-layer frame parsing: pulling the MAC addresses out of a captured frame so they
+//! can be handed straight to [`OuiDb::search`](crate::oui::OuiDb::search).
+
+use eui48::MacAddress;
+use smallvec::SmallVec;
+
+use crate::oui::{OuiMeta, EMBEDDED_DB};
+
+/// Which link-layer framing a captured frame uses.
+///
+/// Named to mirror libpcap's `DLT_*`/`LINKTYPE_*` values, since that's the metadata
+/// a packet-capture consumer will already have on hand for each frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkType {
+    /// DLT_EN10MB - Ethernet II framing.
+    Ethernet,
+    /// DLT_IEEE802_11 - raw IEEE 802.11 framing (no radiotap/prism header).
+    Ieee80211,
+}
+
+/// The IEEE 802.11 Frame Control `Type` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dot11FrameType {
+    Management,
+    Control,
+    Data,
+    Extension,
+}
+impl Dot11FrameType {
+    const fn from_bits(bits: u8) -> Dot11FrameType {
+        match bits {
+            0b00 => Dot11FrameType::Management,
+            0b01 => Dot11FrameType::Control,
+            0b10 => Dot11FrameType::Data,
+            _ => Dot11FrameType::Extension,
+        }
+    }
+}
+
+/// Reads a 6-byte MAC address out of `bytes` at `offset`, returning `None` if the frame
+/// is truncated before the address ends.
+fn mac_at(bytes: &[u8], offset: usize) -> Option<MacAddress> {
+    bytes
+        .get(offset..offset + 6)
+        .map(|b| MacAddress::new(b.try_into().unwrap()))
+}
+
+/// Extracts the destination and source MAC addresses from an Ethernet II header.
+///
+/// Returns only the addresses that were present before the frame was truncated, so a
+/// frame with fewer than 6 bytes yields an empty result and one with 6..12 bytes yields
+/// only the destination.
+fn macs_in_ethernet(bytes: &[u8]) -> SmallVec<[MacAddress; 4]> {
+    let mut found = SmallVec::new();
+    if let Some(dest) = mac_at(bytes, 0) {
+        found.push(dest);
+    }
+    if let Some(src) = mac_at(bytes, 6) {
+        found.push(src);
+    }
+    found
+}
+
+/// Extracts destination/source/BSSID (and, for WDS frames, the fourth address) from an
+/// IEEE 802.11 MAC header.
+///
+/// Decodes the Frame Control field's `type` and the To-DS/From-DS flags to figure out
+/// which of `addr1..addr4` hold the semantic destination/source/BSSID/receiver/
+/// transmitter roles, per the 802.11 standard's address field table. Management frames
+/// always carry `addr1..addr3` (DA/SA/BSSID); Control frames generally carry only
+/// `addr1`, with RTS additionally carrying `addr2`.
+fn macs_in_ieee80211(bytes: &[u8]) -> SmallVec<[MacAddress; 4]> {
+    let mut found = SmallVec::new();
+
+    if bytes.len() < 2 {
+        return found;
+    }
+    let fc0 = bytes[0];
+    let fc1 = bytes[1];
+    let frame_type = Dot11FrameType::from_bits((fc0 >> 2) & 0b11);
+    let to_ds = fc1 & 0b0000_0001 != 0;
+    let from_ds = fc1 & 0b0000_0010 != 0;
+
+    // addr1 starts after the 2-byte Frame Control field and the 2-byte Duration/ID field
+    const ADDR1: usize = 4;
+    const ADDR2: usize = 10;
+    const ADDR3: usize = 16;
+    // addr4 sits after the 2-byte Sequence Control field that follows addr3
+    const ADDR4: usize = 24;
+
+    match frame_type {
+        Dot11FrameType::Control => {
+            // most control frames (ACK, CTS, ...) carry only the receiver address;
+            // RTS additionally carries the transmitter address in addr2
+            if let Some(addr1) = mac_at(bytes, ADDR1) {
+                found.push(addr1);
+            }
+            if let Some(addr2) = mac_at(bytes, ADDR2) {
+                found.push(addr2);
+            }
+        }
+        Dot11FrameType::Management | Dot11FrameType::Data => {
+            let (addr1, addr2, addr3, addr4) = (
+                mac_at(bytes, ADDR1),
+                mac_at(bytes, ADDR2),
+                mac_at(bytes, ADDR3),
+                mac_at(bytes, ADDR4),
+            );
+
+            // following the To-DS/From-DS address-role table (IEEE 802.11-2020 Table 9-26)
+            let roles = match (to_ds, from_ds) {
+                // IBSS / management: addr1=DA, addr2=SA, addr3=BSSID
+                (false, false) => [addr1, addr2, addr3, None],
+                // to AP: addr1=BSSID, addr2=SA, addr3=DA
+                (true, false) => [addr3, addr2, addr1, None],
+                // from AP: addr1=DA, addr2=BSSID, addr3=SA
+                (false, true) => [addr1, addr3, addr2, None],
+                // WDS: addr1=RA, addr2=TA, addr3=DA, addr4=SA - report as-is, there's
+                // no single DA/SA/BSSID framing that covers all four roles here
+                (true, true) => [addr1, addr2, addr3, addr4],
+            };
+            found.extend(roles.into_iter().flatten());
+        }
+        Dot11FrameType::Extension => {
+            if let Some(addr1) = mac_at(bytes, ADDR1) {
+                found.push(addr1);
+            }
+        }
+    }
+
+    found
+}
+
+/// Extracts every MAC address found in a captured frame's link-layer header.
+///
+/// Truncated frames return whichever addresses were parsed before the data ran out,
+/// rather than erroring - a capture consumer generally still wants the partial result.
+pub fn macs_in_frame(bytes: &[u8], link_type: LinkType) -> SmallVec<[MacAddress; 4]> {
+    match link_type {
+        LinkType::Ethernet => macs_in_ethernet(bytes),
+        LinkType::Ieee80211 => macs_in_ieee80211(bytes),
+    }
+}
+
+/// Like [`macs_in_frame`], but resolves each address's vendor against [`EMBEDDED_DB`] in
+/// the same pass.
+pub fn macs_with_vendor_in_frame(
+    bytes: &[u8],
+    link_type: LinkType,
+) -> SmallVec<[(MacAddress, Option<OuiMeta<&'static str>>); 4]> {
+    macs_in_frame(bytes, link_type)
+        .into_iter()
+        .map(|mac| (mac, EMBEDDED_DB.search(mac)))
+        .collect()
+}
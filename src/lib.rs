@@ -0,0 +1,5 @@
+pub mod frame;
+pub mod oui;
+#[cfg(feature = "refresh")]
+pub mod refresh;
+pub mod sqlite;
@@ -1,4 +1,4 @@
-use std::{fmt, num::ParseIntError, str::FromStr, borrow::Cow};
+use std::{fmt, num::ParseIntError, str::FromStr, borrow::Cow, io::{self, Read, Write}, net::Ipv6Addr};
 
 // The default rust 'oui' crate doesn't search efficiently, and we can't use it memory-optimized ways.
 //
@@ -202,6 +202,75 @@ impl Oui {
             length: 6 * 8,
         })
     }
+
+    /// The first octet of the address, where the multicast and U/L bits live.
+    const fn first_octet(&self) -> u8 {
+        (self.address >> 40) as u8
+    }
+
+    /// Whether this is a multicast address - the low bit of the first octet is set.
+    pub const fn is_multicast(&self) -> bool {
+        self.first_octet() & 0b0000_0001 != 0
+    }
+
+    /// Whether this is the all-ones broadcast address (`FF:FF:FF:FF:FF:FF`).
+    pub const fn is_broadcast(&self) -> bool {
+        self.length == 48 && self.address == 0x0000_FFFF_FFFF_FFFF
+    }
+
+    /// Whether this address is locally administered, i.e. the U/L bit (the
+    /// second-lowest bit of the first octet) is set.
+    pub const fn is_local(&self) -> bool {
+        self.first_octet() & 0b0000_0010 != 0
+    }
+
+    /// Whether this address is universally administered (IEEE-assigned), i.e. the
+    /// opposite of [`Oui::is_local`].
+    pub const fn is_universal(&self) -> bool {
+        !self.is_local()
+    }
+
+    /// Converts this 48-bit MAC into a modified EUI-64 interface identifier: inserts
+    /// `FF:FE` between the third and fourth octets, and flips the U/L bit (bit 1 of the
+    /// first octet) as required by the modified-EUI-64 format used in IPv6 SLAAC.
+    pub const fn to_eui64(&self) -> u64 {
+        let high24 = ((self.address >> 24) & 0x00FF_FFFF) ^ 0x0002_0000;
+        let low24 = self.address & 0x00FF_FFFF;
+        (high24 << 40) | (0xFFFE << 24) | low24
+    }
+
+    /// Reverses [`Oui::to_eui64`], recovering the original 48-bit MAC (with the U/L bit
+    /// flipped back) from 8 modified-EUI-64 bytes.
+    ///
+    /// Returns `None` if bytes 3 and 4 aren't the `FF:FE` marker inserted by
+    /// `to_eui64` - i.e. the identifier wasn't derived from a MAC address at all, as is
+    /// the case for IPv6 privacy-extension (RFC 4941) addresses.
+    pub const fn from_eui64_bytes(bytes: [u8; 8]) -> Option<Oui> {
+        if bytes[3] != 0xFF || bytes[4] != 0xFE {
+            return None;
+        }
+        let mac = [
+            bytes[0] ^ 0x02,
+            bytes[1],
+            bytes[2],
+            bytes[5],
+            bytes[6],
+            bytes[7],
+        ];
+        Some(Oui::from_array(mac))
+    }
+}
+
+/// Recovers the MAC address embedded in an IPv6 SLAAC / EUI-64-derived link-local
+/// address's interface identifier, so it can be fed into [`OuiDb::search`].
+///
+/// Returns `None` if the low 64 bits of `addr` don't carry the `FF:FE` modified-EUI-64
+/// marker - this is always the case for privacy-extension (RFC 4941) addresses, which
+/// don't embed a MAC at all.
+pub fn mac_from_ipv6(addr: Ipv6Addr) -> Option<MacAddress> {
+    let octets = addr.octets();
+    let iid: [u8; 8] = octets[8..16].try_into().unwrap();
+    Oui::from_eui64_bytes(iid).map(Oui::as_mac)
 }
 impl FromStr for Oui {
     type Err = ParseOuiError;
@@ -226,6 +295,32 @@ impl FromStr for Oui {
         Ok(address)
     }
 }
+/// The IEEE registry block type a prefix belongs to, derived from its prefix length.
+///
+/// See IEEE's "Guidelines for Use" for the standard block sizes: a `/24` is an
+/// individually-assignable MA-L ("OUI"), `/28` an MA-M, and `/36` an MA-S.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryBlock {
+    /// MAC Address Block Large (`/24`) - the classic "OUI" registration block.
+    MaL,
+    /// MAC Address Block Medium (`/28`).
+    MaM,
+    /// MAC Address Block Small (`/36`).
+    MaS,
+    /// A prefix length not covered by IEEE's standard registry block sizes.
+    Other(u8),
+}
+impl RegistryBlock {
+    pub const fn from_prefix_length(length: u8) -> RegistryBlock {
+        match length {
+            24 => RegistryBlock::MaL,
+            28 => RegistryBlock::MaM,
+            36 => RegistryBlock::MaS,
+            other => RegistryBlock::Other(other),
+        }
+    }
+}
+
 impl fmt::Debug for Oui {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let formatted = MacStyle::Colon.format(self.as_mac(), false);
@@ -263,7 +358,15 @@ fn check_smallstr_size() {
 ///
 /// Lookups should generally be O(log n), as we perform a binary search to locate an OUI prefix, when given a username
 #[derive(Debug, Clone)]
-pub struct OuiDb(Vec<(Oui, OuiMeta<String>)>);
+pub struct OuiDb {
+    entries: Vec<(Oui, OuiMeta<String>)>,
+
+    /// Lowercased `short` name for each entry, sorted for `binary_search`/prefix range
+    /// scans, pointing back into `entries` by index. Built once at parse time so
+    /// [`OuiDb::find_by_short`] and [`OuiDb::prefixes_for_vendor`] don't re-lowercase
+    /// every entry on every call.
+    name_index: Vec<(Box<str>, usize)>,
+}
 
 lazy_static::lazy_static! {
     pub static ref EMBEDDED_DB: OuiDb = {
@@ -360,13 +463,30 @@ impl OuiDb {
         //     .collect();
         // std::fs::write("oui_db_dump.txt", dbg_str).unwrap();
 
-        Ok(OuiDb(v))
+        let name_index = Self::build_name_index(&v);
+
+        Ok(OuiDb {
+            entries: v,
+            name_index,
+        })
+    }
+
+    /// Builds the lowercased-`short`-name index used by [`OuiDb::find_by_short`] and
+    /// [`OuiDb::prefixes_for_vendor`].
+    fn build_name_index(entries: &[(Oui, OuiMeta<String>)]) -> Vec<(Box<str>, usize)> {
+        let mut name_index: Vec<(Box<str>, usize)> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, (_o, om))| (om.short.to_lowercase().into_boxed_str(), i))
+            .collect();
+        name_index.sort();
+        name_index
     }
 
     pub fn search_entry(&self, mac: MacAddress) -> Option<(Oui, OuiMeta<&str>)> {
         let as_oui = Oui::from_addr(mac);
         // eprintln!("searching MAC {:?} with OUI {:?}", mac, as_oui);
-        let base_i = match self.0.binary_search_by_key(&as_oui, |(o, _om)| *o) {
+        let base_i = match self.entries.binary_search_by_key(&as_oui, |(o, _om)| *o) {
             Ok(i) => i, // exact match
             Err(i) => {
                 // should be n-above our desired entry
@@ -379,7 +499,7 @@ impl OuiDb {
         let mut i = base_i;
 
         loop {
-            let (o, om) = self.0.get(i)?;
+            let (o, om) = self.entries.get(i)?;
             if o.contains(&as_oui) {
                 // this is our prefix
                 return Some((*o, om.as_ref()));
@@ -394,7 +514,7 @@ impl OuiDb {
     }
 
     pub fn raw_prefixes(&self) -> impl Iterator<Item = (Oui, OuiMeta<&str>)> {
-        self.0.iter().map(|(o, om)| (*o, om.as_ref()))
+        self.entries.iter().map(|(o, om)| (*o, om.as_ref()))
     }
     pub fn search_prefix(&self, mac: MacAddress) -> Option<Oui> {
         self.search_entry(mac).map(|(p, _)| p)
@@ -402,6 +522,51 @@ impl OuiDb {
     pub fn search(&self, mac: MacAddress) -> Option<OuiMeta<&str>> {
         self.search_entry(mac).map(|(_, om)| om)
     }
+
+    /// Looks up the prefix containing `mac` (via [`OuiDb::search_prefix`]) and returns
+    /// the IEEE registry block type derived from that prefix's length, or `None` when no
+    /// prefix matches. Whether the address itself is locally administered is reported by
+    /// [`Oui::is_local`], not by this method.
+    pub fn registry_block(&self, mac: MacAddress) -> Option<RegistryBlock> {
+        self.search_prefix(mac)
+            .map(|o| RegistryBlock::from_prefix_length(o.length()))
+    }
+
+    /// Finds the prefix whose `short` name matches `short` exactly (case-insensitive),
+    /// via `binary_search` over the precomputed name index.
+    pub fn find_by_short(&self, short: &str) -> Option<(Oui, OuiMeta<&str>)> {
+        let needle = short.to_lowercase();
+        let i = self
+            .name_index
+            .binary_search_by(|(name, _i)| name.as_ref().cmp(needle.as_str()))
+            .ok()?;
+        let (_name, entry_i) = &self.name_index[i];
+        self.entries.get(*entry_i).map(|(o, om)| (*o, om.as_ref()))
+    }
+
+    /// Finds every prefix whose `short` or `long` name contains `needle`
+    /// (case-insensitive), e.g. to answer "which OUI blocks does Cisco own?"
+    ///
+    /// Walks `name_index` rather than `entries` so the `short` side of the match reuses
+    /// the lowercased name already computed at parse time instead of re-lowercasing
+    /// every entry on every call. There's no equivalent index over `long` - a substring
+    /// match against it can't be served by a sorted-prefix index - so that side still
+    /// lowercases each entry's `long` field on the fly.
+    pub fn prefixes_for_vendor<'a>(
+        &'a self,
+        needle: &str,
+    ) -> impl Iterator<Item = (Oui, OuiMeta<&'a str>)> + 'a {
+        let needle = needle.to_lowercase();
+        self.name_index.iter().filter_map(move |(short_lower, entry_i)| {
+            let (o, om) = &self.entries[*entry_i];
+            let matches = short_lower.contains(needle.as_str())
+                || om
+                    .long
+                    .as_deref()
+                    .is_some_and(|l| l.to_lowercase().contains(&needle));
+            matches.then(|| (*o, om.as_ref()))
+        })
+    }
 }
 impl FromStr for OuiDb {
     type Err = ParseOuiDbError;
@@ -410,6 +575,187 @@ impl FromStr for OuiDb {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum SerializeOuiDbError {
+    #[error("I/O error while writing OUI database: {0}")]
+    Io(#[from] io::Error),
+    #[error("field {0:?} is {1} bytes long, which is too long to encode (max 254)")]
+    FieldTooLong(String, usize),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeserializeOuiDbError {
+    #[error("I/O error while reading OUI database: {0}")]
+    Io(#[from] io::Error),
+    #[error("bad magic bytes in OUI database header: expected {expected:?}, got {found:?}")]
+    BadMagic { expected: [u8; 4], found: [u8; 4] },
+    #[error("unsupported OUI database format version {0}, this build only understands version {}", OuiDb::FORMAT_VERSION)]
+    UnsupportedVersion(u8),
+    #[error("entry {0} is not in ascending order relative to the previous entry - binary search would be invalid")]
+    OutOfOrder(usize),
+    #[error("field at entry {0} is not valid UTF-8: {1}")]
+    InvalidUtf8(usize, #[source] std::string::FromUtf8Error),
+    #[error("entry {0} has an out-of-range prefix length {1} (expected 24..=48)")]
+    InvalidLength(usize, u8),
+}
+
+impl OuiDb {
+    /// Magic bytes identifying the compact binary `OuiDb` format.
+    const FORMAT_MAGIC: [u8; 4] = *b"OUI1";
+    /// Version of the compact binary `OuiDb` format written by [`OuiDb::serialize`].
+    const FORMAT_VERSION: u8 = 1;
+
+    /// Writes this database out in a compact, length-prefixed binary form that preserves
+    /// the existing sorted order, so [`OuiDb::deserialize`] can load it straight back into
+    /// the backing `Vec` without re-parsing text or re-sorting.
+    pub fn serialize<W: Write>(&self, mut w: W) -> Result<(), SerializeOuiDbError> {
+        w.write_all(&Self::FORMAT_MAGIC)?;
+        w.write_all(&[Self::FORMAT_VERSION])?;
+        w.write_all(&(self.entries.len() as u32).to_le_bytes())?;
+
+        for (oui, meta) in &self.entries {
+            let len_bytes = (oui.length as usize + 7) / 8;
+            let addr_be = oui.address.to_be_bytes();
+
+            w.write_all(&[oui.length])?;
+            w.write_all(&addr_be[2..2 + len_bytes])?;
+
+            Self::write_field(&mut w, Some(meta.short.as_str()))?;
+            Self::write_field(&mut w, meta.long.as_deref())?;
+            Self::write_field(&mut w, meta.comment.as_deref())?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a single optional field as a `u8` length prefix followed by its UTF-8 bytes.
+    /// `0` means "absent" (`None`), so a present-but-empty string is encoded as length `1`
+    /// with zero following bytes.
+    fn write_field<W: Write>(w: &mut W, field: Option<&str>) -> Result<(), SerializeOuiDbError> {
+        match field {
+            None => w.write_all(&[0])?,
+            Some(s) => {
+                let encoded_len = s.len() + 1;
+                if encoded_len > u8::MAX as usize {
+                    return Err(SerializeOuiDbError::FieldTooLong(s.to_owned(), s.len()));
+                }
+                w.write_all(&[encoded_len as u8])?;
+                w.write_all(s.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads back a database written by [`OuiDb::serialize`].
+    ///
+    /// Verifies that prefixes are monotonically increasing as they're read, since
+    /// [`OuiDb::search_entry`]'s binary search relies on that ordering - a stream that
+    /// fails this check is rejected rather than silently producing bad lookups.
+    pub fn deserialize<R: Read>(mut r: R) -> Result<OuiDb, DeserializeOuiDbError> {
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if magic != Self::FORMAT_MAGIC {
+            return Err(DeserializeOuiDbError::BadMagic {
+                expected: Self::FORMAT_MAGIC,
+                found: magic,
+            });
+        }
+
+        let mut version = [0u8; 1];
+        r.read_exact(&mut version)?;
+        if version[0] != Self::FORMAT_VERSION {
+            return Err(DeserializeOuiDbError::UnsupportedVersion(version[0]));
+        }
+
+        let mut count_buf = [0u8; 4];
+        r.read_exact(&mut count_buf)?;
+        let count = u32::from_le_bytes(count_buf) as usize;
+
+        // `count` comes straight off an untrusted stream - don't let a corrupted header
+        // drive a multi-gigabyte pre-allocation before we've read a single entry. The
+        // `Vec` still grows past this if the stream genuinely holds more entries.
+        const MAX_PREALLOCATED_ENTRIES: usize = 1 << 16;
+        let mut v = Vec::with_capacity(count.min(MAX_PREALLOCATED_ENTRIES));
+        let mut prev: Option<Oui> = None;
+        for i in 0..count {
+            let mut length_buf = [0u8; 1];
+            r.read_exact(&mut length_buf)?;
+            let length = length_buf[0];
+            if !(24..=48).contains(&length) {
+                return Err(DeserializeOuiDbError::InvalidLength(i, length));
+            }
+            let len_bytes = (length as usize + 7) / 8;
+
+            let mut addr_be = [0u8; 8];
+            r.read_exact(&mut addr_be[2..2 + len_bytes])?;
+            let address = u64::from_be_bytes(addr_be);
+            let oui = Oui { address, length };
+
+            if matches!(prev, Some(p) if oui <= p) {
+                return Err(DeserializeOuiDbError::OutOfOrder(i));
+            }
+            prev = Some(oui);
+
+            let short = Self::read_field(&mut r, i)?.unwrap_or_default();
+            let long = Self::read_field(&mut r, i)?;
+            let comment = Self::read_field(&mut r, i)?;
+
+            v.push((oui, OuiMeta { short, long, comment }));
+        }
+
+        let name_index = Self::build_name_index(&v);
+
+        Ok(OuiDb {
+            entries: v,
+            name_index,
+        })
+    }
+
+    /// Reads a single optional field written by [`OuiDb::write_field`].
+    fn read_field<R: Read>(r: &mut R, entry: usize) -> Result<Option<String>, DeserializeOuiDbError> {
+        let mut len_buf = [0u8; 1];
+        r.read_exact(&mut len_buf)?;
+        match len_buf[0] {
+            0 => Ok(None),
+            encoded_len => {
+                let mut buf = vec![0u8; encoded_len as usize - 1];
+                r.read_exact(&mut buf)?;
+                let s = String::from_utf8(buf)
+                    .map_err(|e| DeserializeOuiDbError::InvalidUtf8(entry, e))?;
+                Ok(Some(s))
+            }
+        }
+    }
+}
+
+#[test]
+fn serialize_deserialize_roundtrip() {
+    let mut buf = Vec::new();
+    EMBEDDED_DB.serialize(&mut buf).unwrap();
+    let restored = OuiDb::deserialize(buf.as_slice()).unwrap();
+
+    assert_eq!(EMBEDDED_DB.entries.len(), restored.entries.len());
+    assert_eq!(EMBEDDED_DB.entries, restored.entries);
+}
+
+#[test]
+fn deserialize_rejects_bad_magic() {
+    let err = OuiDb::deserialize(&b"nope"[..]).unwrap_err();
+    assert!(matches!(err, DeserializeOuiDbError::BadMagic { .. }));
+}
+
+#[test]
+fn deserialize_rejects_out_of_range_length_instead_of_panicking() {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&OuiDb::FORMAT_MAGIC);
+    buf.push(OuiDb::FORMAT_VERSION);
+    buf.extend_from_slice(&1u32.to_le_bytes()); // one entry
+    buf.push(49); // out of the valid 24..=48 range
+
+    let err = OuiDb::deserialize(buf.as_slice()).unwrap_err();
+    assert!(matches!(err, DeserializeOuiDbError::InvalidLength(0, 49)));
+}
+
 #[test]
 fn embedded_db_builds() {
     OuiDb::parse_from_string(OuiDb::WIRESHARK_OUI_DB_EMBEDDED).unwrap();
@@ -528,6 +874,89 @@ fn resolve_mac_to_superprefix_when_missing_subprefix() {
     );
 }
 
+#[test]
+fn classification_bits() {
+    let universal = Oui::from_int(0x0000_1700_0000).unwrap(); // 00:00:17
+    assert!(!universal.is_multicast());
+    assert!(!universal.is_local());
+    assert!(universal.is_universal());
+    assert!(!universal.is_broadcast());
+
+    let multicast_local = Oui::from_int(0x0300_0000_0000).unwrap(); // 03:00:00, bits 0 and 1 set
+    assert!(multicast_local.is_multicast());
+    assert!(multicast_local.is_local());
+    assert!(!multicast_local.is_universal());
+
+    let broadcast = Oui::from_int(0x0000_FFFF_FFFF_FFFF).unwrap();
+    assert!(broadcast.is_broadcast());
+}
+
+#[test]
+fn registry_block_from_matched_prefix_length() {
+    // 2C:23:3A	HewlettP	Hewlett Packard (/24)
+    let mac_24 = parse_mac_addr("2c:23:3a:aa:bb:cc").unwrap();
+    assert_eq!(EMBEDDED_DB.registry_block(mac_24), Some(RegistryBlock::MaL));
+
+    // 8C:47:6E:30:00:00/28	Shanghai
+    let mac_28 = parse_mac_addr("8c:47:6e:3a:bb:cc").unwrap();
+    assert_eq!(EMBEDDED_DB.registry_block(mac_28), Some(RegistryBlock::MaM));
+
+    // 8C:1F:64:CB:20:00/36	DyncirSo
+    let mac_36 = parse_mac_addr("8c:1f:64:cb:2b:cc").unwrap();
+    assert_eq!(EMBEDDED_DB.registry_block(mac_36), Some(RegistryBlock::MaS));
+}
+
+#[test]
+fn find_by_short_exact_case_insensitive() {
+    let (oui, om) = EMBEDDED_DB.find_by_short("hewlettp").unwrap();
+    assert_eq!(om.manuf(), &"HewlettP");
+    assert_eq!(oui.length(), 24);
+
+    assert!(EMBEDDED_DB.find_by_short("not-a-real-vendor-name").is_none());
+}
+
+#[test]
+fn prefixes_for_vendor_substring_match() {
+    let found: Vec<_> = EMBEDDED_DB.prefixes_for_vendor("hewlett").collect();
+    assert!(!found.is_empty());
+    assert!(found.iter().all(|(_o, om)| om.manuf().to_lowercase().contains("hewlett")
+        || om
+            .manuf_long()
+            .is_some_and(|l| l.to_lowercase().contains("hewlett"))));
+}
+
+#[test]
+fn eui64_roundtrip() {
+    let mac = parse_mac_addr("2c:23:3a:aa:bb:cc").unwrap();
+    let oui = Oui::from_addr(mac);
+
+    let eui64 = oui.to_eui64();
+    let eui64_bytes = eui64.to_be_bytes();
+    assert_eq!(&eui64_bytes[3..5], &[0xFF, 0xFE]);
+    // U/L bit of the first octet should have been flipped
+    assert_eq!(eui64_bytes[0], 0x2c ^ 0x02);
+
+    let recovered = Oui::from_eui64_bytes(eui64_bytes).unwrap();
+    assert_eq!(recovered, oui);
+}
+
+#[test]
+fn from_eui64_bytes_rejects_missing_marker() {
+    let not_eui64 = [0x2e, 0x23, 0x3a, 0x00, 0x00, 0xaa, 0xbb, 0xcc];
+    assert_eq!(Oui::from_eui64_bytes(not_eui64), None);
+}
+
+#[test]
+fn mac_from_ipv6_slaac_address() {
+    // fe80::2e23:3aff:feaa:bbcc is the SLAAC address for MAC 2c:23:3a:aa:bb:cc
+    let addr: Ipv6Addr = "fe80::2e23:3aff:feaa:bbcc".parse().unwrap();
+    let mac = mac_from_ipv6(addr).unwrap();
+    assert_eq!(mac, parse_mac_addr("2c:23:3a:aa:bb:cc").unwrap());
+
+    let non_eui64: Ipv6Addr = "2001:db8::1".parse().unwrap();
+    assert_eq!(mac_from_ipv6(non_eui64), None);
+}
+
 #[test]
 fn match_none() {
     // B0:C5:59	SamsungE	Samsung Electronics Co.,Ltd
@@ -0,0 +1,111 @@
+//! Runtime refresh of the OUI database from Wireshark's upstream `manuf` file.
+//!
+//! [`OuiDb::WIRESHARK_OUI_DB_EMBEDDED`](crate::oui::OuiDb::WIRESHARK_OUI_DB_EMBEDDED) is
+//! only as fresh as the crate release that embedded it. This module lets a long-running
+//! process fetch an up-to-date copy and hot-swap it in via [`SharedOuiDb`] without
+//! dropping in-flight [`search`](crate::oui::OuiDb::search) callers.
+//!
+//! Requires the `refresh` feature.
+
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use arc_swap::ArcSwap;
+
+use crate::oui::{OuiDb, ParseOuiDbError};
+
+/// The Wireshark gitlab URL [`OuiDb::WIRESHARK_OUI_DB_EMBEDDED`] is itself sourced from.
+pub const DEFAULT_MANUF_URL: &str = "https://gitlab.com/wireshark/wireshark/raw/master/manuf";
+
+#[derive(Debug, thiserror::Error)]
+pub enum FetchOuiDbError {
+    #[error("error fetching OUI database from {1:?}: {0}")]
+    Http(#[source] ureq::Error, String),
+    #[error("I/O error while reading/writing the OUI database cache: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Parsing(#[from] ParseOuiDbError),
+}
+
+impl OuiDb {
+    /// Downloads the upstream Wireshark `manuf` file and parses it, defaulting to
+    /// [`DEFAULT_MANUF_URL`] when `url` is `None`.
+    pub fn fetch_latest(url: Option<&str>) -> Result<OuiDb, FetchOuiDbError> {
+        let url = url.unwrap_or(DEFAULT_MANUF_URL);
+        let body = ureq::get(url)
+            .call()
+            .map_err(|e| FetchOuiDbError::Http(e, url.to_owned()))?
+            .into_string()?;
+        Ok(OuiDb::parse_from_string(&body)?)
+    }
+
+    /// Loads a database from a local cache file at `path`, re-downloading from `url`
+    /// (see [`OuiDb::fetch_latest`]) and overwriting the cache when it's missing or
+    /// older than `max_age`.
+    pub fn load_or_cache(
+        path: &Path,
+        url: Option<&str>,
+        max_age: Duration,
+    ) -> Result<OuiDb, FetchOuiDbError> {
+        let is_stale = match std::fs::metadata(path).and_then(|m| m.modified()) {
+            Ok(modified) => modified.elapsed().unwrap_or(Duration::MAX) > max_age,
+            Err(_) => true, // missing, or we can't tell - treat as stale
+        };
+
+        if !is_stale {
+            if let Ok(cached) = std::fs::read_to_string(path) {
+                if let Ok(db) = OuiDb::parse_from_string(&cached) {
+                    return Ok(db);
+                }
+                // fall through and re-fetch if the cached copy doesn't parse
+            }
+        }
+
+        let url = url.unwrap_or(DEFAULT_MANUF_URL);
+        let body = ureq::get(url)
+            .call()
+            .map_err(|e| FetchOuiDbError::Http(e, url.to_owned()))?
+            .into_string()?;
+        let db = OuiDb::parse_from_string(&body)?;
+
+        std::fs::write(path, &body)?;
+
+        Ok(db)
+    }
+}
+
+/// An `OuiDb` that can be atomically swapped out from under in-flight readers.
+///
+/// Wraps an [`ArcSwap`] rather than a `RwLock` so [`SharedOuiDb::load`] never blocks on
+/// a concurrent [`SharedOuiDb::store`] - a long-running service can refresh vendor data
+/// in the background without stalling lookups in flight.
+#[derive(Debug)]
+pub struct SharedOuiDb(ArcSwap<OuiDb>);
+
+impl SharedOuiDb {
+    pub fn new(db: OuiDb) -> SharedOuiDb {
+        SharedOuiDb(ArcSwap::from_pointee(db))
+    }
+
+    /// Returns a handle to the current database. Holding onto the returned `Arc` keeps
+    /// reading from the same snapshot even if [`SharedOuiDb::store`] is called
+    /// concurrently.
+    pub fn load(&self) -> Arc<OuiDb> {
+        self.0.load_full()
+    }
+
+    /// Atomically replaces the current database with `db`.
+    pub fn store(&self, db: OuiDb) {
+        self.0.store(Arc::new(db));
+    }
+}
+
+impl Default for SharedOuiDb {
+    /// Starts out pointing at the compile-time embedded database.
+    fn default() -> SharedOuiDb {
+        SharedOuiDb::new(crate::oui::EMBEDDED_DB.clone())
+    }
+}
@@ -0,0 +1,158 @@
+//! SQLite bindings for the embedded OUI database.
+//!
+//! Registers four scalar functions (`oui_manuf`, `oui_manuf_long`, `oui_comment`,
+//! `oui_prefix`) and an eponymous-only virtual table (`oui_prefixes`) against a
+//! [`rusqlite::Connection`], so SQL consumers can resolve or enumerate vendor
+//! prefixes without leaving the database.
+
+use std::os::raw::{c_char, c_int};
+
+use rusqlite::{
+    functions::FunctionFlags,
+    types::ValueRef,
+    vtab::{eponymous_only_module, Context, IndexInfo, VTab, VTabConnection, VTabCursor, Values},
+    Connection, Error as SqliteError, Result as SqliteResult,
+};
+
+use crate::oui::{parse_mac_addr, Oui, OuiMeta, EMBEDDED_DB};
+
+/// Parses a MAC address out of a SQL parameter, accepting either a TEXT value (fed
+/// through [`parse_mac_addr`]) or an INTEGER value (fed through [`Oui::from_int`]).
+fn mac_from_param(value: ValueRef<'_>) -> SqliteResult<eui48::MacAddress> {
+    match value {
+        ValueRef::Text(bytes) => {
+            let s = std::str::from_utf8(bytes)
+                .map_err(|e| SqliteError::Utf8Error(e))?;
+            parse_mac_addr(s)
+                .map_err(|e| SqliteError::UserFunctionError(Box::new(e)))
+        }
+        ValueRef::Integer(i) => Oui::from_int(i as u64)
+            .map(Oui::as_mac)
+            .map_err(|e| SqliteError::UserFunctionError(Box::new(e))),
+        _ => Err(SqliteError::InvalidFunctionParameterType(
+            0,
+            value.data_type(),
+        )),
+    }
+}
+
+/// Registers the `oui_manuf`, `oui_manuf_long`, `oui_comment` and `oui_prefix` scalar
+/// functions, plus the `oui_prefixes` virtual table, on `conn`.
+pub fn register(conn: &Connection) -> SqliteResult<()> {
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+
+    conn.create_scalar_function("oui_manuf", 1, flags, |ctx| {
+        let mac = mac_from_param(ctx.get_raw(0))?;
+        Ok(EMBEDDED_DB.search(mac).map(|om| om.manuf().to_owned()))
+    })?;
+    conn.create_scalar_function("oui_manuf_long", 1, flags, |ctx| {
+        let mac = mac_from_param(ctx.get_raw(0))?;
+        Ok(EMBEDDED_DB
+            .search(mac)
+            .and_then(|om| om.manuf_long().map(str::to_owned)))
+    })?;
+    conn.create_scalar_function("oui_comment", 1, flags, |ctx| {
+        let mac = mac_from_param(ctx.get_raw(0))?;
+        Ok(EMBEDDED_DB
+            .search(mac)
+            .and_then(|om| om.comment().map(str::to_owned)))
+    })?;
+    conn.create_scalar_function("oui_prefix", 1, flags, |ctx| {
+        let mac = mac_from_param(ctx.get_raw(0))?;
+        Ok(EMBEDDED_DB.search_prefix(mac).map(|o| format!("{:#?}", o)))
+    })?;
+
+    conn.create_module::<OuiPrefixesTab>("oui_prefixes", eponymous_only_module::<OuiPrefixesTab>(), None)?;
+
+    Ok(())
+}
+
+/// Virtual table yielding one row per entry in [`EMBEDDED_DB`]'s [`raw_prefixes`](crate::oui::OuiDb::raw_prefixes).
+#[repr(C)]
+struct OuiPrefixesTab {
+    base: rusqlite::vtab::sqlite3_vtab,
+}
+
+unsafe impl<'vtab> VTab<'vtab> for OuiPrefixesTab {
+    type Aux = ();
+    type Cursor = OuiPrefixesCursor;
+
+    fn connect(
+        _db: &mut VTabConnection,
+        _aux: Option<&Self::Aux>,
+        _args: &[&[u8]],
+    ) -> SqliteResult<(String, Self)> {
+        let sql = "CREATE TABLE x(prefix TEXT, length INTEGER, address INTEGER, short TEXT, long TEXT, comment TEXT)".to_owned();
+        Ok((sql, OuiPrefixesTab { base: rusqlite::vtab::sqlite3_vtab::default() }))
+    }
+
+    fn best_index(&self, info: &mut IndexInfo) -> SqliteResult<()> {
+        info.set_estimated_cost(EMBEDDED_DB.raw_prefixes().count() as f64);
+        Ok(())
+    }
+
+    fn open(&'vtab mut self) -> SqliteResult<Self::Cursor> {
+        let rows: Vec<(Oui, OuiMeta<String>)> = EMBEDDED_DB
+            .raw_prefixes()
+            .map(|(o, om)| (o, om.to_owned()))
+            .collect();
+        Ok(OuiPrefixesCursor { rows, index: 0 })
+    }
+}
+
+#[repr(C)]
+struct OuiPrefixesCursor {
+    rows: Vec<(Oui, OuiMeta<String>)>,
+    index: usize,
+}
+
+unsafe impl VTabCursor for OuiPrefixesCursor {
+    fn filter(&mut self, _idx_num: c_int, _idx_str: Option<&str>, _args: &Values<'_>) -> SqliteResult<()> {
+        self.index = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> SqliteResult<()> {
+        self.index += 1;
+        Ok(())
+    }
+
+    fn eof(&self) -> bool {
+        self.index >= self.rows.len()
+    }
+
+    fn column(&self, ctx: &mut Context, col: c_int) -> SqliteResult<()> {
+        let (oui, om) = &self.rows[self.index];
+        match col {
+            0 => ctx.set_result(&format!("{:#?}", oui)),
+            1 => ctx.set_result(&(oui.length() as i64)),
+            2 => ctx.set_result(&(oui.as_int() as i64)),
+            3 => ctx.set_result(om.manuf()),
+            4 => ctx.set_result(&om.manuf_long()),
+            5 => ctx.set_result(&om.comment()),
+            _ => Err(SqliteError::InvalidColumnIndex(col as usize)),
+        }
+    }
+
+    fn rowid(&self) -> SqliteResult<i64> {
+        Ok(self.index as i64)
+    }
+}
+
+/// Entry point that `sqlite3_auto_extension`/the SQLite loadable-extension ABI can call
+/// to register every function and table in this module on newly opened connections.
+///
+/// # Safety
+/// Must only be invoked by SQLite itself (or `rusqlite::LoadExtensionGuard`) with a
+/// valid `db` handle and API routine table, per the loadable-extension ABI.
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_nettools_init(
+    db: *mut rusqlite::ffi::sqlite3,
+    _pz_err_msg: *mut *mut c_char,
+    p_api: *mut rusqlite::ffi::sqlite3_api_routines,
+) -> c_int {
+    rusqlite::ffi::extension_init2(db, std::ptr::null_mut(), p_api, |conn| {
+        register(conn)?;
+        Ok(false)
+    })
+}